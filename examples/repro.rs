@@ -0,0 +1,48 @@
+use hermitdb::crdts::{map, orswot, Dot, VClock, CmRDT};
+use hermitdb::{memory_log, LogReplicable};
+
+type TActor = u8;
+type TKey = u8;
+type TVal = hermitdb::crdts::Orswot<u8, TActor>;
+type TMap = hermitdb::crdts::Map<TKey, TVal, TActor>;
+type TOp = map::Op<TKey, TVal, TActor>;
+
+fn main() {
+    let a_ops: Vec<TOp> = vec![
+        map::Op::Up { dot: Dot::new(98, 9), key: 224, op: orswot::Op::Add { member: 208, dot: Dot::new(98, 9) } },
+        map::Op::Rm { context: vec![(98u8, 9u64)].into_iter().collect(), key: 224 },
+    ];
+    let b_ops: Vec<TOp> = vec![
+        map::Op::Up { dot: Dot::new(64, 5), key: 224, op: orswot::Op::Rm { context: VClock::new(), member: 55 } },
+    ];
+
+    let mut a_log: memory_log::Log<TActor, TMap> = memory_log::Log::new();
+    let mut b_log: memory_log::Log<TActor, TMap> = memory_log::Log::new();
+    let mut c_log: memory_log::Log<TActor, TMap> = memory_log::Log::new();
+    let mut a_map = TMap::new();
+    let mut b_map = TMap::new();
+
+    for op in a_ops.clone() {
+        let t = a_log.commit(98, op).unwrap();
+        a_map.apply(t.op()).unwrap();
+        a_log.ack(&t).unwrap();
+    }
+    println!("a_map after own ops: {:#?}", a_map);
+    for op in b_ops.clone() {
+        let t = b_log.commit(64, op).unwrap();
+        b_map.apply(t.op()).unwrap();
+        b_log.ack(&t).unwrap();
+    }
+
+    a_log.push(&mut c_log).unwrap();
+    b_log.push(&mut c_log).unwrap();
+    a_log.pull(&c_log).unwrap();
+    b_log.pull(&c_log).unwrap();
+
+    while let Some(t) = a_log.next().unwrap() {
+        println!("a drains {:?}", t);
+        a_map.apply(t.op()).unwrap();
+        a_log.ack(&t).unwrap();
+    }
+    println!("a_map final (centralized): {:#?}", a_map);
+}
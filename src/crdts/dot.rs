@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// A `Dot` is a version marker for a single actor: the `counter`th operation
+/// produced by `actor`. Dots are how a causal CRDT knows whether it has
+/// already seen a given mutation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Dot<A> {
+    /// The actor that produced this version.
+    pub actor: A,
+    /// The sequence number of this version, starting at 1.
+    pub counter: u64,
+}
+
+impl<A> Dot<A> {
+    /// Build a dot from an actor and a counter.
+    pub fn new(actor: A, counter: u64) -> Self {
+        Self { actor, counter }
+    }
+}
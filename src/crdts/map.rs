@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
+use crate::crdts::dot::Dot;
+use crate::crdts::traits::{CmRDT, Error, ResetRemove};
+use crate::crdts::vclock::VClock;
+
+/// A map with observed-remove semantics: values are themselves CRDTs, and a
+/// key survives a concurrent remove/update race the same way an
+/// [`crate::crdts::orswot::Orswot`] member does.
+///
+/// A removed key's entry is never dropped from `entries`, only reset to an
+/// empty causal context: the nested value CRDT can carry bookkeeping (e.g.
+/// an inner [`crate::crdts::orswot::Orswot`]'s own clock) that has to keep
+/// accumulating across a remove/update race, or two replicas that apply the
+/// same ops in different orders end up with different (if equally "empty")
+/// states. [`Map::get`] hides these tombstoned entries.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound(serialize = "K: Serialize, V: Serialize, A: Serialize"))]
+#[serde(bound(deserialize = "K: Deserialize<'de>, V: Deserialize<'de>, A: Deserialize<'de>"))]
+pub struct Map<K: Ord, V, A: Ord> {
+    entries: BTreeMap<K, (V, VClock<A>)>,
+    clock: VClock<A>,
+}
+
+impl<K: Ord, V, A: Ord + Clone> Default for Map<K, V, A> {
+    fn default() -> Self {
+        Map { entries: BTreeMap::new(), clock: VClock::new() }
+    }
+}
+
+/// A mutation to a [`Map`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Op<K, V: CmRDT, A: Ord> {
+    /// No change. Useful as a heartbeat or a replication placeholder.
+    Nop,
+    /// Remove `key`, dropping every dot `context` has already observed.
+    Rm {
+        /// The causal context the remove was issued under.
+        context: VClock<A>,
+        /// The key being removed.
+        key: K,
+    },
+    /// Apply `op` to the value stored at `key`, tagging the update with
+    /// `dot`.
+    Up {
+        /// The dot this update is tagged with.
+        dot: Dot<A>,
+        /// The key being updated.
+        key: K,
+        /// The op to apply to the value at `key`.
+        op: V::Op,
+    },
+}
+
+impl<K: Ord + Clone, V: CmRDT + Clone + Default, A: Ord + Clone> Map<K, V, A> {
+    /// Build an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The next dot `actor` would use to tag a new update.
+    pub fn dot(&self, actor: A) -> Dot<A> {
+        Dot::new(actor.clone(), self.clock.get(&actor) + 1)
+    }
+
+    /// Read the current value and causal context stored at `key`, if any.
+    /// Returns `None` for a key that was removed, even though its tombstone
+    /// is still kept internally.
+    pub fn get(&self, key: &K) -> Option<(V, VClock<A>)> {
+        self.entries.get(key).filter(|(_, entry_clock)| !entry_clock.is_empty()).cloned()
+    }
+
+    /// Build an update of the value at `key`: `f` is handed a clone of the
+    /// current value (or a fresh default one) along with `dot`, and must
+    /// return the op it performed on it. This does not mutate `self` — the
+    /// resulting [`Op::Up`] still has to go through `apply`, locally and
+    /// remotely, like any other op.
+    pub fn update<F>(&self, key: K, dot: Dot<A>, f: F) -> Op<K, V, A>
+    where
+        F: FnOnce(&mut V, Dot<A>) -> V::Op,
+    {
+        let mut value = self.entries.get(&key).map(|(v, _)| v.clone()).unwrap_or_default();
+        let op = f(&mut value, dot.clone());
+        Op::Up { dot, key, op }
+    }
+
+    /// Build a remove of `key` under `ctx`.
+    pub fn rm(&self, key: impl Into<K>, ctx: VClock<A>) -> Op<K, V, A> {
+        Op::Rm { context: ctx, key: key.into() }
+    }
+}
+
+impl<
+        K: Ord + Clone + Debug,
+        V: CmRDT<Error = Error> + ResetRemove<A> + Clone + Default + Debug,
+        A: Ord + Clone + Debug,
+    > CmRDT for Map<K, V, A>
+{
+    type Op = Op<K, V, A>;
+    type Error = Error;
+
+    fn apply(&mut self, op: &Self::Op) -> Result<(), Self::Error> {
+        match op {
+            Op::Nop => (),
+            Op::Rm { context, key } => {
+                let (value, entry_clock) =
+                    self.entries.entry(key.clone()).or_insert_with(|| (V::default(), VClock::new()));
+                value.reset_remove(context);
+                *entry_clock = entry_clock.subtract(context);
+                self.clock.merge(context);
+            }
+            Op::Up { dot, key, op } => {
+                if !self.clock.has_seen(dot) {
+                    let (value, entry_clock) =
+                        self.entries.entry(key.clone()).or_insert_with(|| (V::default(), VClock::new()));
+                    value.apply(op)?;
+                    entry_clock.apply(dot);
+                    self.clock.apply(dot);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<
+        K: Ord + Clone + Debug,
+        V: CmRDT<Error = Error> + ResetRemove<A> + Clone + Default + Debug,
+        A: Ord + Clone + Debug,
+    > ResetRemove<A> for Map<K, V, A>
+{
+    fn reset_remove(&mut self, clock: &VClock<A>) {
+        for (value, entry_clock) in self.entries.values_mut() {
+            value.reset_remove(clock);
+            *entry_clock = entry_clock.subtract(clock);
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//! A small, self-contained library of the CRDTs hermitdb replicates over its
+//! [`crate::LogReplicable`] backends: an observed-remove set ([`Orswot`])
+//! nested inside an observed-remove map ([`Map`]), versioned with vector
+//! clocks ([`VClock`]/[`Dot`]).
+
+mod dot;
+mod traits;
+mod vclock;
+
+pub mod map;
+pub mod orswot;
+
+pub use dot::Dot;
+pub use map::Map;
+pub use orswot::Orswot;
+pub use traits::{CmRDT, Error, ResetRemove};
+pub use vclock::VClock;
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+use serde::{Deserialize, Serialize};
+
+use crate::crdts::dot::Dot;
+use crate::crdts::traits::{CmRDT, Error, ResetRemove};
+use crate::crdts::vclock::VClock;
+
+/// An add-biased, observed-remove set: members survive a concurrent
+/// remove/add race, which is what makes this safe to use as the value type
+/// of a [`crate::crdts::map::Map`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Orswot<M: Ord, A: Ord> {
+    /// Each live member, tagged with the dots under which it was added.
+    entries: BTreeMap<M, VClock<A>>,
+    /// The causal history of every add/remove this set has applied.
+    clock: VClock<A>,
+}
+
+impl<M: Ord, A: Ord + Clone> Default for Orswot<M, A> {
+    fn default() -> Self {
+        Orswot { entries: BTreeMap::new(), clock: VClock::new() }
+    }
+}
+
+/// A mutation to an [`Orswot`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Op<M, A: Ord> {
+    /// Add `member` under `dot`.
+    Add {
+        /// The member being added.
+        member: M,
+        /// The dot this add is tagged with.
+        dot: Dot<A>,
+    },
+    /// Remove every dot of `member` that `context` has already observed.
+    Rm {
+        /// The causal context the remove was issued under.
+        context: VClock<A>,
+        /// The member being removed.
+        member: M,
+    },
+}
+
+impl<M: Ord + Clone + Debug, A: Ord + Clone + Debug> Orswot<M, A> {
+    /// Build an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The causal context under which `member` is currently present, i.e.
+    /// what a subsequent `remove` of `member` should carry as its context.
+    pub fn context(&self, member: &M) -> VClock<A> {
+        self.entries.get(member).cloned().unwrap_or_default()
+    }
+
+    /// Add `member` under `dot`, applying the resulting op to `self` and
+    /// returning it so it can be replicated to other actors.
+    pub fn add(&mut self, member: M, dot: Dot<A>) -> Op<M, A> {
+        let op = Op::Add { member, dot };
+        self.apply(&op).expect("Orswot::apply is infallible");
+        op
+    }
+
+    /// Build a remove of `member` under `ctx`. Unlike `add`, this does not
+    /// mutate `self` directly: the resulting op still has to travel through
+    /// `apply` (locally and remotely) like any other op.
+    pub fn remove(&self, member: M, ctx: VClock<A>) -> Op<M, A> {
+        Op::Rm { context: ctx, member }
+    }
+}
+
+impl<M: Ord + Clone + Debug, A: Ord + Clone + Debug> CmRDT for Orswot<M, A> {
+    type Op = Op<M, A>;
+    type Error = Error;
+
+    fn apply(&mut self, op: &Self::Op) -> Result<(), Self::Error> {
+        match op {
+            Op::Add { member, dot } => {
+                if !self.clock.has_seen(dot) {
+                    let entry = self.entries.entry(member.clone()).or_default();
+                    entry.apply(dot);
+                    self.clock.apply(dot);
+                }
+            }
+            Op::Rm { context, member } => {
+                if let Some(entry) = self.entries.get(member) {
+                    let survivors = entry.subtract(context);
+                    if survivors.is_empty() {
+                        self.entries.remove(member);
+                    } else {
+                        self.entries.insert(member.clone(), survivors);
+                    }
+                }
+                self.clock.merge(context);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<M: Ord + Clone + Debug, A: Ord + Clone + Debug> ResetRemove<A> for Orswot<M, A> {
+    fn reset_remove(&mut self, clock: &VClock<A>) {
+        self.entries.retain(|_, entry_clock| {
+            *entry_clock = entry_clock.subtract(clock);
+            !entry_clock.is_empty()
+        });
+    }
+}
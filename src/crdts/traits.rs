@@ -0,0 +1,43 @@
+use std::fmt::Debug;
+
+use crate::crdts::vclock::VClock;
+
+/// Operation-based CRDTs replicate by transmitting each operation to every
+/// replica. As long as operations from a single actor are applied in the
+/// order they were generated, and every replica eventually applies every
+/// operation, all replicas converge to the same state.
+///
+/// Every CRDT in this module is built so that `apply` is infallible in
+/// practice: operations are idempotent and commutative by construction.
+/// `Error` is kept around (rather than dropping the `Result`) so that a
+/// future CRDT with runtime invariants it can't encode in the type system
+/// can plug into the same trait without changing every caller.
+pub trait CmRDT {
+    /// The mutation this CRDT knows how to apply.
+    type Op: Debug;
+
+    /// The error a failed `apply` would report.
+    type Error: Debug;
+
+    /// Apply an op to this CRDT.
+    fn apply(&mut self, op: &Self::Op) -> Result<(), Self::Error>;
+}
+
+/// The uninhabited error type shared by the CRDTs in this module: none of
+/// their `apply` implementations can actually fail, so no value of this
+/// type is ever constructed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {}
+
+/// A CRDT that can push a causal remove down into its own state, rather
+/// than only being removable at the granularity of "replace the whole
+/// value". [`crate::crdts::map::Map`] needs this from its value type: when a
+/// key is removed under a context that only partially dominates the key's
+/// entry clock (a concurrent update under another actor survives), the
+/// value has to shed whatever that context already observed, or replicas
+/// that saw the concurrent update via different paths end up holding
+/// different remnants of the same key.
+pub trait ResetRemove<A: Ord> {
+    /// Drop every dot `clock` has already observed from this CRDT's state.
+    fn reset_remove(&mut self, clock: &VClock<A>);
+}
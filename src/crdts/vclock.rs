@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+use serde::{Deserialize, Serialize};
+
+use crate::crdts::dot::Dot;
+
+/// A vector clock: for each actor we've observed, the counter of the most
+/// recent op from that actor that has been folded into this clock.
+///
+/// `VClock` is used both as a causal context (to know what an actor has
+/// seen) and as a dominance check (to know whether an op is stale).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VClock<A: Ord> {
+    dots: BTreeMap<A, u64>,
+}
+
+impl<A: Ord> Default for VClock<A> {
+    fn default() -> Self {
+        VClock { dots: BTreeMap::new() }
+    }
+}
+
+impl<A: Ord + Clone> VClock<A> {
+    /// Build an empty vector clock.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The counter this clock has observed for `actor`, or `0` if none.
+    pub fn get(&self, actor: &A) -> u64 {
+        self.dots.get(actor).copied().unwrap_or(0)
+    }
+
+    /// True if this clock has already observed `dot` (i.e. applying it
+    /// again would be a no-op).
+    pub fn has_seen(&self, dot: &Dot<A>) -> bool {
+        self.get(&dot.actor) >= dot.counter
+    }
+
+    /// Fold a single dot into this clock, advancing the actor's counter if
+    /// the dot is newer than what we've already seen.
+    pub fn apply(&mut self, dot: &Dot<A>) {
+        let counter = self.dots.entry(dot.actor.clone()).or_insert(0);
+        if dot.counter > *counter {
+            *counter = dot.counter;
+        }
+    }
+
+    /// Merge another clock into this one, taking the max counter per actor.
+    pub fn merge(&mut self, other: &VClock<A>) {
+        for (actor, counter) in other.dots.iter() {
+            let entry = self.dots.entry(actor.clone()).or_insert(0);
+            if *counter > *entry {
+                *entry = *counter;
+            }
+        }
+    }
+
+    /// True if this clock has no dots.
+    pub fn is_empty(&self) -> bool {
+        self.dots.is_empty()
+    }
+
+    /// The dots in `self` that are not already dominated by `other`, i.e.
+    /// what survives of `self` after subtracting the causal history in
+    /// `other`. Used to implement observed-remove: a concurrent re-add
+    /// (a dot `other` hasn't seen) survives a remove carrying `other` as
+    /// its context.
+    pub fn subtract(&self, other: &VClock<A>) -> VClock<A> {
+        let dots = self
+            .dots
+            .iter()
+            .filter(|(actor, counter)| **counter > other.get(actor))
+            .map(|(actor, counter)| (actor.clone(), *counter))
+            .collect();
+        VClock { dots }
+    }
+}
+
+impl<A: Ord + Clone> FromIterator<(A, u64)> for VClock<A> {
+    fn from_iter<T: IntoIterator<Item = (A, u64)>>(iter: T) -> Self {
+        let mut clock = VClock::new();
+        for (actor, counter) in iter {
+            clock.apply(&Dot::new(actor, counter));
+        }
+        clock
+    }
+}
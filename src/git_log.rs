@@ -0,0 +1,637 @@
+//! A durable [`LogReplicable`] backend backed by a git repository. Ops
+//! accumulate into blocks, bincode-encoded and batched onto one linear ref
+//! per actor (`refs/heads/hermitdb/log/<actor>`), so the log survives a
+//! restart, replicates as whole blocks, and can be inspected or backed up
+//! with ordinary git tooling.
+//!
+//! This is considerably heavier than [`crate::memory_log`]: every flushed
+//! block does an fsync'd object write, and `next` re-walks every actor's
+//! commit chain on each call (decoded blocks are memoized in an LRU cache
+//! keyed by OID, so a re-walk only decodes blocks that aren't cached).
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use std::fmt;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+
+use crate::crdts::{CmRDT, Dot};
+use crate::{LogReplicable, TaggedOp};
+
+const REF_PREFIX: &str = "refs/heads/hermitdb/log";
+
+/// Where [`Log::pull_remote`] expects fetched refs to land: pass a refspec
+/// whose destination side is this prefix, e.g.
+/// `"+refs/heads/hermitdb/log/*:refs/hermitdb/remote-tracking/*"`
+/// (see [`Log::default_fetch_refspec`]).
+pub const REMOTE_TRACKING_PREFIX: &str = "refs/hermitdb/remote-tracking";
+
+/// Default capacity of the decoded-object cache (see [`Log::with_cache_capacity`]).
+const DEFAULT_CACHE_CAPACITY: usize = 64;
+
+/// A credentials callback, in the shape `git2::RemoteCallbacks::credentials`
+/// wants: given the remote URL, the username the URL suggested (if any), and
+/// the kinds of credentials the server will accept, produce one.
+pub type CredentialCallback =
+    Box<dyn Fn(&str, Option<&str>, git2::CredentialType) -> Result<git2::Cred, git2::Error>>;
+
+/// A remote this log can fetch from and push to, and how to authenticate.
+struct RemoteConfig {
+    url: String,
+    credentials: CredentialCallback,
+}
+
+/// Everything that can go wrong talking to the underlying git repository.
+#[derive(Debug)]
+pub enum Error {
+    /// The git repository itself reported an error.
+    Git(git2::Error),
+    /// An op couldn't be encoded/decoded.
+    Json(serde_json::Error),
+    /// An actor identifier or a block couldn't be bincode encoded/decoded.
+    Bincode(bincode::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Git(e) => write!(f, "git error: {}", e),
+            Error::Json(e) => write!(f, "encoding error: {}", e),
+            Error::Bincode(e) => write!(f, "bincode encoding error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<git2::Error> for Error {
+    fn from(e: git2::Error) -> Self {
+        Error::Git(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+impl From<bincode::Error> for Error {
+    fn from(e: bincode::Error) -> Self {
+        Error::Bincode(e)
+    }
+}
+
+/// Block format version. Bincode lays out a struct's fields in order with
+/// no padding, so this is also the blob's first byte on disk; bumping it on
+/// an incompatible format change is what lets [`decode_block`] tell a block
+/// it can't decode from a pre-chunk0-6 repo (see [`StoredOp`]).
+const BLOCK_FORMAT_VERSION: u8 = 1;
+
+/// How many ops [`Log::commit`] batches into one pending block before
+/// flushing it as a single git commit. Consecutive `commit` calls that
+/// aren't interleaved with a `next`/`pull`/`push` (which flush to make
+/// pending ops visible to replication) land in the same block, so a log of
+/// N small ops produces on the order of N / `BLOCK_CAPACITY` git objects
+/// instead of N.
+const BLOCK_CAPACITY: usize = 32;
+
+/// The content stored in each commit's `op` blob: a batch of consecutively
+/// committed ops plus the counter the first one was committed under
+/// (`ops[i]` was committed under `base_counter + i as u64`), so decoding
+/// just the tip blob is how we learn an actor's current counter without
+/// walking its whole chain.
+#[derive(Serialize, Deserialize)]
+struct Block<Op> {
+    version: u8,
+    base_counter: u64,
+    ops: Vec<Op>,
+}
+
+impl<Op> Block<Op> {
+    /// The counter the last op in this block was committed under.
+    fn tip_counter(&self) -> u64 {
+        self.base_counter + self.ops.len() as u64 - 1
+    }
+}
+
+/// The pre-chunk0-6 on-disk format: one op per commit, JSON encoded.
+/// [`decode_block`] falls back to this so existing repos keep working,
+/// migrating each such commit into a single-op [`Block`] on read.
+#[derive(Serialize, Deserialize)]
+struct StoredOp<Op> {
+    counter: u64,
+    op: Op,
+}
+
+fn encode_block<Op: Serialize>(block: &Block<Op>) -> Result<Vec<u8>, Error> {
+    Ok(bincode::serialize(block)?)
+}
+
+fn decode_block<Op: for<'de> Deserialize<'de>>(bytes: &[u8]) -> Result<Block<Op>, Error> {
+    if let Ok(block) = bincode::deserialize::<Block<Op>>(bytes) {
+        if block.version == BLOCK_FORMAT_VERSION {
+            return Ok(block);
+        }
+    }
+    let legacy: StoredOp<Op> = serde_json::from_slice(bytes)?;
+    Ok(Block { version: BLOCK_FORMAT_VERSION, base_counter: legacy.counter, ops: vec![legacy.op] })
+}
+
+/// A candidate commit in [`Log::ordered_tagged_ops`]'s priority walk,
+/// ordered so a max-heap pops the newest (and, on a timestamp tie, the
+/// greatest-OID) candidate first. `actor` rides along for free so the
+/// popped commit can be re-tagged without a second ref lookup, but doesn't
+/// participate in the ordering.
+struct TimeOrderedCommit<A> {
+    time: i64,
+    oid: git2::Oid,
+    actor: A,
+}
+
+impl<A> PartialEq for TimeOrderedCommit<A> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.time, self.oid) == (other.time, other.oid)
+    }
+}
+
+impl<A> Eq for TimeOrderedCommit<A> {}
+
+impl<A> PartialOrd for TimeOrderedCommit<A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<A> Ord for TimeOrderedCommit<A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.time, self.oid).cmp(&(other.time, other.oid))
+    }
+}
+
+/// The single most recently looked-up `(oid, block)` pair (see
+/// `Log::last`).
+type LastBlock<Op> = Option<(git2::Oid, Rc<Block<Op>>)>;
+
+/// A git-backed, per-actor append log.
+pub struct Log<A, M: CmRDT> {
+    repo: git2::Repository,
+    name: String,
+    root: String,
+    acked: BTreeSet<(A, u64)>,
+    /// Ops committed since the last flush, not yet written as a block, keyed
+    /// by the actor that committed them. Kept behind a `RefCell` (rather
+    /// than requiring `&mut self`) so `pull` can flush `other`'s pending ops
+    /// before transplanting its objects, even though `other` is only
+    /// borrowed immutably.
+    pending: RefCell<BTreeMap<A, Vec<M::Op>>>,
+    /// Decoded `Block`s, keyed by the git OID they were read from. Git
+    /// objects are immutable and content-addressed, so a decode is valid
+    /// forever once cached; `flush` inserts its own freshly-built entry
+    /// instead of waiting for a future `next`/`pull` to decode it again.
+    cache: RefCell<LruCache<git2::Oid, Rc<Block<M::Op>>>>,
+    /// The single most recently looked-up OID, checked before the LRU so
+    /// the common case of repeatedly re-reading the same tip doesn't even
+    /// pay for an LRU touch.
+    last: RefCell<LastBlock<M::Op>>,
+    /// The remote this log talks to over the network, if it was built with
+    /// [`Log::with_credentials`]. Absent for purely local logs.
+    remote: Option<RemoteConfig>,
+    _map: PhantomData<M>,
+}
+
+impl<A, M: CmRDT> fmt::Debug for Log<A, M>
+where
+    A: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("git_log::Log")
+            .field("name", &self.name)
+            .field("root", &self.root)
+            .field("acked", &self.acked)
+            .field("pending", &self.pending.borrow().values().map(Vec::len).sum::<usize>())
+            .field("remote", &self.remote.as_ref().map(|r| &r.url))
+            .finish()
+    }
+}
+
+impl<A, M> Log<A, M>
+where
+    A: Ord + Clone + Serialize + for<'de> Deserialize<'de>,
+    M: CmRDT,
+    M::Op: Clone + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Build a log on top of an already-opened bare `repo`, without any
+    /// credentials wired up for remote transports. `name` is a
+    /// human-readable label (used in commit messages); `root` is the path
+    /// `repo` lives at on disk.
+    pub fn no_auth(repo: git2::Repository, name: String, root: String) -> Self {
+        Self::with_cache_capacity(repo, name, root, DEFAULT_CACHE_CAPACITY)
+    }
+
+    /// Like [`Log::no_auth`], but with the decoded-object cache sized to
+    /// hold `cache_capacity` entries instead of the default.
+    pub fn with_cache_capacity(
+        repo: git2::Repository,
+        name: String,
+        root: String,
+        cache_capacity: usize,
+    ) -> Self {
+        Self {
+            repo,
+            name,
+            root,
+            acked: BTreeSet::new(),
+            pending: RefCell::new(BTreeMap::new()),
+            cache: RefCell::new(LruCache::new(cache_capacity)),
+            last: RefCell::new(None),
+            remote: None,
+            _map: PhantomData,
+        }
+    }
+
+    /// Build a log like [`Log::no_auth`], but able to talk to `url` over the
+    /// network: [`Log::pull_remote`] fetches from it and [`Log::push_remote`]
+    /// pushes to it, authenticating with `credentials` (wired into git2's
+    /// `RemoteCallbacks::credentials`, so it can hand back an ssh key,
+    /// user/pass, or defer to the ssh-agent).
+    pub fn with_credentials(
+        repo: git2::Repository,
+        name: String,
+        root: String,
+        url: String,
+        credentials: CredentialCallback,
+    ) -> Self {
+        let mut log = Self::with_cache_capacity(repo, name, root, DEFAULT_CACHE_CAPACITY);
+        log.remote = Some(RemoteConfig { url, credentials });
+        log
+    }
+
+    /// The path this log's repository lives at.
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// The refspec [`Log::pull_remote`] should be called with to fetch every
+    /// actor's ops into this log's remote-tracking namespace.
+    pub fn default_fetch_refspec() -> String {
+        format!("+{}/*:{}/*", REF_PREFIX, REMOTE_TRACKING_PREFIX)
+    }
+
+    /// Encode `ops` (the ops committed starting at `base_counter`) into the
+    /// on-disk bytes one block's `op` blob holds. Exposed so the block
+    /// format can be round-tripped without a real repository.
+    pub fn encode_ops(base_counter: u64, ops: Vec<M::Op>) -> Result<Vec<u8>, Error> {
+        encode_block(&Block { version: BLOCK_FORMAT_VERSION, base_counter, ops })
+    }
+
+    /// The inverse of [`Log::encode_ops`]: decode a block blob back into its
+    /// base counter and ops. Also used to migrate pre-chunk0-6 repos, which
+    /// stored one JSON-encoded op per commit instead of a block.
+    pub fn decode_ops(bytes: &[u8]) -> Result<(u64, Vec<M::Op>), Error> {
+        let block = decode_block(bytes)?;
+        Ok((block.base_counter, block.ops))
+    }
+
+    fn remote_config(&self) -> Result<&RemoteConfig, Error> {
+        self.remote.as_ref().ok_or_else(|| {
+            Error::Git(git2::Error::from_str(
+                "no remote configured; build this log with `with_credentials`",
+            ))
+        })
+    }
+
+    fn remote_callbacks(remote_cfg: &RemoteConfig) -> git2::RemoteCallbacks<'_> {
+        let mut callbacks = git2::RemoteCallbacks::new();
+        callbacks.credentials(move |url, username, allowed| {
+            (remote_cfg.credentials)(url, username, allowed)
+        });
+        callbacks
+    }
+
+    /// Fetch `refspec` from this log's remote (see [`Log::with_credentials`])
+    /// and adopt any actor whose remote tip is ahead of what we have: for
+    /// every ref under [`REMOTE_TRACKING_PREFIX`] the fetch lands, if its
+    /// counter is higher than our local tip for that actor, our local ref
+    /// is fast-forwarded to it (the remote's objects are already in our odb
+    /// once the fetch completes, so there's nothing left to copy).
+    pub fn pull_remote(&mut self, refspec: &str) -> Result<(), Error> {
+        {
+            let remote_cfg = self.remote_config()?;
+            let mut remote = self.repo.remote_anonymous(&remote_cfg.url)?;
+            let mut fetch_opts = git2::FetchOptions::new();
+            fetch_opts.remote_callbacks(Self::remote_callbacks(remote_cfg));
+            remote.fetch(&[refspec], Some(&mut fetch_opts), None)?;
+        }
+
+        let mut tracked = Vec::new();
+        for reference in self.repo.references_glob(&format!("{}/*", REMOTE_TRACKING_PREFIX))? {
+            let reference = reference?;
+            if let (Some(name), Some(oid)) = (reference.name(), reference.target()) {
+                if let Some(actor) = Self::actor_from_ref_prefix(name, REMOTE_TRACKING_PREFIX) {
+                    tracked.push((actor, oid));
+                }
+            }
+        }
+
+        for (actor, remote_tip) in tracked {
+            self.adopt_remote_tip(&actor, remote_tip)?;
+        }
+        Ok(())
+    }
+
+    /// Push every actor ref this log knows about to its remote, under the
+    /// same `refs/heads/hermitdb/log/*` names it uses locally, so another
+    /// hermitdb replica can fetch or clone it like any other log. Unlike
+    /// [`Log::pull_remote`]'s fetch, libgit2 doesn't support a wildcard
+    /// refspec on push, so this lists one explicit `actor:actor` refspec per
+    /// known actor rather than a single glob.
+    pub fn push_remote(&mut self) -> Result<(), Error> {
+        let refs: Vec<String> = self
+            .known_actors()?
+            .iter()
+            .map(Self::ref_name)
+            .collect::<Result<_, Error>>()?;
+        if refs.is_empty() {
+            return Ok(());
+        }
+
+        let remote_cfg = self.remote_config()?;
+        let mut remote = self.repo.remote_anonymous(&remote_cfg.url)?;
+        let mut push_opts = git2::PushOptions::new();
+        push_opts.remote_callbacks(Self::remote_callbacks(remote_cfg));
+        let refspecs: Vec<String> = refs.iter().map(|r| format!("+{}:{}", r, r)).collect();
+        let refspecs: Vec<&str> = refspecs.iter().map(String::as_str).collect();
+        remote.push(&refspecs, Some(&mut push_opts))?;
+        Ok(())
+    }
+
+    /// If `remote_tip`'s counter is ahead of `actor`'s local tip, fast-forward
+    /// `actor`'s local ref to it. The fetch that discovered `remote_tip` has
+    /// already copied its objects into our odb.
+    fn adopt_remote_tip(&mut self, actor: &A, remote_tip: git2::Oid) -> Result<(), Error> {
+        let remote_counter = self.block_at(&self.repo.find_commit(remote_tip)?)?.tip_counter();
+        if remote_counter > self.counter_of(actor)? {
+            self.repo.reference(&Self::ref_name(actor)?, remote_tip, true, "hermitdb pull (remote)")?;
+        }
+        Ok(())
+    }
+
+    fn ref_name(actor: &A) -> Result<String, Error> {
+        let bytes = bincode::serialize(actor)?;
+        Ok(format!("{}/{}", REF_PREFIX, hex_encode(&bytes)))
+    }
+
+    fn actor_from_ref_prefix(name: &str, prefix: &str) -> Option<A> {
+        let hex = name.strip_prefix(prefix)?.strip_prefix('/')?;
+        let bytes = hex_decode(hex)?;
+        bincode::deserialize(&bytes).ok()
+    }
+
+    fn known_actors(&self) -> Result<BTreeSet<A>, Error> {
+        let mut actors = BTreeSet::new();
+        for reference in self.repo.references_glob(&format!("{}/*", REF_PREFIX))? {
+            let reference = reference?;
+            if let Some(name) = reference.name() {
+                if let Some(actor) = Self::actor_from_ref_prefix(name, REF_PREFIX) {
+                    actors.insert(actor);
+                }
+            }
+        }
+        Ok(actors)
+    }
+
+    fn tip_oid(&self, actor: &A) -> Result<Option<git2::Oid>, Error> {
+        match self.repo.find_reference(&Self::ref_name(actor)?) {
+            Ok(r) => Ok(r.target()),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn block_at(&self, commit: &git2::Commit<'_>) -> Result<Rc<Block<M::Op>>, Error> {
+        let oid = commit.id();
+
+        if let Some((last_oid, block)) = self.last.borrow().as_ref() {
+            if *last_oid == oid {
+                return Ok(Rc::clone(block));
+            }
+        }
+        if let Some(block) = self.cache.borrow_mut().get(&oid) {
+            let block = Rc::clone(block);
+            *self.last.borrow_mut() = Some((oid, Rc::clone(&block)));
+            return Ok(block);
+        }
+
+        let tree = commit.tree()?;
+        let entry = tree.get_name("op").ok_or_else(|| {
+            Error::Git(git2::Error::from_str("hermitdb commit missing `op` blob"))
+        })?;
+        let blob = entry.to_object(&self.repo)?.peel_to_blob()?;
+        let block = Rc::new(decode_block(blob.content())?);
+
+        self.cache_insert(oid, Rc::clone(&block));
+        Ok(block)
+    }
+
+    /// Memoize a decoded block under `oid`, in both the hot single-entry
+    /// slot and the LRU behind it.
+    fn cache_insert(&self, oid: git2::Oid, block: Rc<Block<M::Op>>) {
+        self.cache.borrow_mut().put(oid, Rc::clone(&block));
+        *self.last.borrow_mut() = Some((oid, block));
+    }
+
+    /// Write every actor's pending ops as a new block each, committed as a
+    /// single git commit on top of that actor's current tip. Takes `&self`
+    /// (backed by `RefCell`s) rather than `&mut self` so `pull` can flush
+    /// `other`'s pending ops through a shared reference before transplanting
+    /// its objects.
+    fn flush(&self) -> Result<(), Error> {
+        let actors: Vec<A> = self.pending.borrow().keys().cloned().collect();
+        for actor in actors {
+            self.flush_one(&actor)?;
+        }
+        Ok(())
+    }
+
+    /// Write `actor`'s pending ops as one new block, committed as a single
+    /// git commit on top of `actor`'s current tip. A no-op if nothing is
+    /// pending for `actor`.
+    fn flush_one(&self, actor: &A) -> Result<(), Error> {
+        let ops = match self.pending.borrow_mut().get_mut(actor) {
+            Some(pending) if !pending.is_empty() => pending.split_off(0),
+            _ => return Ok(()),
+        };
+        let base_counter = self.counter_of(actor)? + 1;
+        let tip_counter = base_counter + ops.len() as u64 - 1;
+        let block = Block { version: BLOCK_FORMAT_VERSION, base_counter, ops };
+        let blob_oid = self.repo.blob(&encode_block(&block)?)?;
+
+        let mut builder = self.repo.treebuilder(None)?;
+        builder.insert("op", blob_oid, git2::FileMode::Blob.into())?;
+        let tree_oid = builder.write()?;
+        let tree = self.repo.find_tree(tree_oid)?;
+
+        let parent = self.tip_oid(actor)?.map(|oid| self.repo.find_commit(oid)).transpose()?;
+        let parents: Vec<&git2::Commit<'_>> = parent.iter().collect();
+
+        let sig = git2::Signature::now(&self.name, "hermitdb@localhost")?;
+        let message = format!("{} block {}..={}", self.name, base_counter, tip_counter);
+        let commit_oid = self.repo.commit(None, &sig, &sig, &message, &tree, &parents)?;
+        self.repo.reference(&Self::ref_name(actor)?, commit_oid, true, "hermitdb commit")?;
+        self.cache_insert(commit_oid, Rc::new(block));
+        Ok(())
+    }
+
+    /// Walk every known actor's chain in one globally deterministic order:
+    /// seed a max-heap with each actor's tip commit, keyed on
+    /// `(commit_time, oid)`, then repeatedly pop the greatest, tag it, and
+    /// push its parent (if any) back in under the same actor. Reversing the
+    /// resulting newest-first sequence gives ops oldest-first, with every
+    /// commit emitted only after all the commits it descends from -- and
+    /// since the ordering key ties are broken strictly on OID bytes, two
+    /// replicas that have fetched the same commits always agree on it,
+    /// which is a stronger guarantee than relying on CRDT commutativity
+    /// alone to converge.
+    fn ordered_tagged_ops(&self) -> Result<Vec<TaggedOp<A, M::Op>>, Error> {
+        let mut heap = BinaryHeap::new();
+        for actor in self.known_actors()? {
+            if let Some(oid) = self.tip_oid(&actor)? {
+                let time = self.repo.find_commit(oid)?.time().seconds();
+                heap.push(TimeOrderedCommit { time, oid, actor });
+            }
+        }
+
+        let mut newest_first = Vec::new();
+        while let Some(TimeOrderedCommit { oid, actor, .. }) = heap.pop() {
+            let commit = self.repo.find_commit(oid)?;
+            let block = self.block_at(&commit)?;
+            // Pushed tip-down so that the final `reverse()` below restores
+            // this block's own ops to ascending (oldest-first) order.
+            for i in (0..block.ops.len()).rev() {
+                let counter = block.base_counter + i as u64;
+                newest_first.push(TaggedOp::new(Dot::new(actor.clone(), counter), block.ops[i].clone()));
+            }
+
+            if let Ok(parent_oid) = commit.parent_id(0) {
+                let time = self.repo.find_commit(parent_oid)?.time().seconds();
+                heap.push(TimeOrderedCommit { time, oid: parent_oid, actor });
+            }
+        }
+
+        newest_first.reverse();
+        Ok(newest_first)
+    }
+
+    fn counter_of(&self, actor: &A) -> Result<u64, Error> {
+        match self.tip_oid(actor)? {
+            None => Ok(0),
+            Some(oid) => Ok(self.block_at(&self.repo.find_commit(oid)?)?.tip_counter()),
+        }
+    }
+
+    /// Copy every object reachable from `other`'s tip commit for `actor`
+    /// that isn't already in `self`'s object database, then advance
+    /// `self`'s ref for `actor` to match.
+    fn transplant(&mut self, other: &Self, actor: &A, other_tip: git2::Oid) -> Result<(), Error> {
+        let odb = self.repo.odb()?;
+        let other_odb = other.repo.odb()?;
+
+        let mut missing = Vec::new();
+        let mut next = Some(other_tip);
+        while let Some(oid) = next {
+            if odb.exists(oid) {
+                break;
+            }
+            missing.push(oid);
+            let commit = other.repo.find_commit(oid)?;
+            let tree = commit.tree()?;
+            if let Some(entry) = tree.get_name("op") {
+                missing.push(entry.id());
+            }
+            missing.push(tree.id());
+            next = commit.parent_id(0).ok();
+        }
+
+        for oid in missing {
+            if odb.exists(oid) {
+                continue;
+            }
+            let object = other_odb.read(oid)?;
+            odb.write(object.kind(), object.data())?;
+        }
+
+        self.repo.reference(&Self::ref_name(actor)?, other_tip, true, "hermitdb pull")?;
+        Ok(())
+    }
+}
+
+impl<A, M> LogReplicable<A, M> for Log<A, M>
+where
+    A: Ord + Clone + Serialize + for<'de> Deserialize<'de>,
+    M: CmRDT,
+    M::Op: Clone + Serialize + for<'de> Deserialize<'de>,
+{
+    type Error = Error;
+
+    fn commit(&mut self, actor: A, op: M::Op) -> Result<TaggedOp<A, M::Op>, Self::Error> {
+        let pending_before = self.pending.borrow().get(&actor).map_or(0, Vec::len);
+        let counter = self.counter_of(&actor)? + pending_before as u64 + 1;
+        self.pending.borrow_mut().entry(actor.clone()).or_default().push(op.clone());
+        if pending_before + 1 >= BLOCK_CAPACITY {
+            self.flush_one(&actor)?;
+        }
+        Ok(TaggedOp::new(Dot::new(actor, counter), op))
+    }
+
+    fn ack(&mut self, tagged_op: &TaggedOp<A, M::Op>) -> Result<(), Self::Error> {
+        let dot = tagged_op.dot();
+        self.acked.insert((dot.actor.clone(), dot.counter));
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<Option<TaggedOp<A, M::Op>>, Self::Error> {
+        self.flush()?;
+        for tagged in self.ordered_tagged_ops()? {
+            let dot = tagged.dot();
+            if !self.acked.contains(&(dot.actor.clone(), dot.counter)) {
+                return Ok(Some(tagged));
+            }
+        }
+        Ok(None)
+    }
+
+    fn pull(&mut self, other: &Self) -> Result<(), Self::Error> {
+        other.flush()?;
+        for actor in other.known_actors()? {
+            if let Some(other_tip) = other.tip_oid(&actor)? {
+                if other.counter_of(&actor)? > self.counter_of(&actor)? {
+                    self.transplant(other, &actor, other_tip)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, other: &mut Self) -> Result<(), Self::Error> {
+        other.pull(self)
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_decode(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
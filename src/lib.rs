@@ -0,0 +1,77 @@
+//! hermitdb replicates a CRDT by treating every mutation as an entry in an
+//! append-only, per-actor log. A [`LogReplicable`] backend only has to know
+//! how to commit an op, track which ops the local replica has acknowledged,
+//! and exchange unseen ops with another log of the same kind; the CRDT
+//! itself (see [`crdts`]) takes care of converging once the ops arrive.
+
+use std::fmt::Debug;
+
+pub use git2;
+
+pub mod crdts;
+pub mod git_log;
+pub mod memory_log;
+pub mod op_heads;
+#[cfg(feature = "rocks")]
+pub mod rocks_log;
+
+use crate::crdts::Dot;
+
+/// An op, tagged with the dot under which it was committed. This is the
+/// unit of replication: backends exchange `TaggedOp`s instead of raw CRDT
+/// ops so that a receiver can tell which actor and counter produced them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaggedOp<A, Op> {
+    dot: Dot<A>,
+    op: Op,
+}
+
+impl<A, Op> TaggedOp<A, Op> {
+    /// Build a tagged op from a dot and the op it tags.
+    pub fn new(dot: Dot<A>, op: Op) -> Self {
+        Self { dot, op }
+    }
+
+    /// The actor and counter this op was committed under.
+    pub fn dot(&self) -> &Dot<A> {
+        &self.dot
+    }
+
+    /// The op itself.
+    pub fn op(&self) -> &Op {
+        &self.op
+    }
+}
+
+/// A replicated, append-only log of CRDT ops.
+///
+/// A backend stores every op it has committed or received, remembers which
+/// of those ops the local replica has acknowledged (i.e. already folded into
+/// its materialized CRDT), and can exchange the ops another log of the same
+/// kind hasn't seen yet. The actor committing is passed at call time rather
+/// than baked into the log at construction, so a log that only ever relays
+/// other actors' ops (e.g. a pull/push relay) never needs a meaningless
+/// actor id of its own.
+pub trait LogReplicable<A, M: crdts::CmRDT> {
+    /// The error this backend's IO can fail with.
+    type Error: Debug;
+
+    /// Commit `op` on behalf of `actor`, tagging it with the next dot for
+    /// that actor, and return the tagged op so the caller can fold it into
+    /// its own materialized CRDT.
+    fn commit(&mut self, actor: A, op: M::Op) -> Result<TaggedOp<A, M::Op>, Self::Error>;
+
+    /// Mark `tagged_op` as acknowledged: the caller has already applied it,
+    /// so `next` should not hand it back again.
+    fn ack(&mut self, tagged_op: &TaggedOp<A, M::Op>) -> Result<(), Self::Error>;
+
+    /// Return the next unacknowledged op, if any, in a deterministic order.
+    fn next(&mut self) -> Result<Option<TaggedOp<A, M::Op>>, Self::Error>;
+
+    /// Pull every op `other` has that `self` doesn't, leaving them
+    /// unacknowledged so a subsequent `next`/`ack` loop picks them up.
+    fn pull(&mut self, other: &Self) -> Result<(), Self::Error>;
+
+    /// Push every op `self` has that `other` doesn't.
+    fn push(&mut self, other: &mut Self) -> Result<(), Self::Error>;
+}
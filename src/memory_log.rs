@@ -0,0 +1,100 @@
+//! A volatile, in-process [`LogReplicable`] backend. Useful for tests and for
+//! actors that don't need their log to survive a restart.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::crdts::CmRDT;
+use crate::{LogReplicable, TaggedOp};
+
+/// An in-memory, per-actor append log.
+pub struct Log<A, M: CmRDT> {
+    /// Every op this replica has committed or pulled in, keyed by the actor
+    /// that produced it, ordered by that actor's counter (index 0 is
+    /// counter 1).
+    ops: BTreeMap<A, Vec<M::Op>>,
+    /// The (actor, counter) pairs this replica has already folded into its
+    /// materialized CRDT.
+    acked: BTreeSet<(A, u64)>,
+    _map: PhantomData<M>,
+}
+
+impl<A, M: CmRDT> fmt::Debug for Log<A, M>
+where
+    A: fmt::Debug,
+    M::Op: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("memory_log::Log")
+            .field("ops", &self.ops)
+            .field("acked", &self.acked)
+            .finish()
+    }
+}
+
+impl<A: Ord + Clone, M: CmRDT> Log<A, M>
+where
+    M::Op: Clone,
+{
+    /// Build an empty log.
+    pub fn new() -> Self {
+        Self { ops: BTreeMap::new(), acked: BTreeSet::new(), _map: PhantomData }
+    }
+}
+
+impl<A: Ord + Clone, M: CmRDT> Default for Log<A, M>
+where
+    M::Op: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A: Ord + Clone, M: CmRDT> LogReplicable<A, M> for Log<A, M>
+where
+    M::Op: Clone,
+{
+    type Error = std::convert::Infallible;
+
+    fn commit(&mut self, actor: A, op: M::Op) -> Result<TaggedOp<A, M::Op>, Self::Error> {
+        let actor_ops = self.ops.entry(actor.clone()).or_default();
+        let counter = actor_ops.len() as u64 + 1;
+        actor_ops.push(op.clone());
+        Ok(TaggedOp::new(crate::crdts::Dot::new(actor, counter), op))
+    }
+
+    fn ack(&mut self, tagged_op: &TaggedOp<A, M::Op>) -> Result<(), Self::Error> {
+        let dot = tagged_op.dot();
+        self.acked.insert((dot.actor.clone(), dot.counter));
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<Option<TaggedOp<A, M::Op>>, Self::Error> {
+        for (actor, actor_ops) in self.ops.iter() {
+            for (i, op) in actor_ops.iter().enumerate() {
+                let counter = i as u64 + 1;
+                if !self.acked.contains(&(actor.clone(), counter)) {
+                    let dot = crate::crdts::Dot::new(actor.clone(), counter);
+                    return Ok(Some(TaggedOp::new(dot, op.clone())));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    fn pull(&mut self, other: &Self) -> Result<(), Self::Error> {
+        for (actor, other_ops) in other.ops.iter() {
+            let our_ops = self.ops.entry(actor.clone()).or_default();
+            if other_ops.len() > our_ops.len() {
+                our_ops.extend_from_slice(&other_ops[our_ops.len()..]);
+            }
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, other: &mut Self) -> Result<(), Self::Error> {
+        other.pull(self)
+    }
+}
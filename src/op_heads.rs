@@ -0,0 +1,219 @@
+//! A meta-history of `commit`/`pull`/`push` calls on a [`LogReplicable`]
+//! log.
+//!
+//! [`Heads`] wraps a log and the CRDT it materializes, and records every
+//! mutating call as an [`OpHead`] pointing at the vector clock before and
+//! after it. Because the ops a log replicates are commutative and
+//! idempotent, undoing to an earlier head never has to delete anything:
+//! [`Heads::restore`] just recomputes the materialized CRDT from the ops
+//! this history has retained, up to the counters recorded at that head.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::crdts::{CmRDT, VClock};
+use crate::{LogReplicable, TaggedOp};
+
+/// What kind of call an [`OpHead`] was recorded for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpKind {
+    /// A local [`Heads::commit`].
+    Commit,
+    /// A [`Heads::pull`] that pulled ops in from another history.
+    Pull,
+    /// A [`Heads::push`] from another history that pushed ops into this
+    /// one (the head is recorded on the receiving side).
+    Push,
+}
+
+/// One entry in the meta-history: the vector clock immediately before and
+/// after a `commit`/`pull`/`push` call.
+#[derive(Debug, Clone)]
+pub struct OpHead<A: Ord> {
+    id: u64,
+    kind: OpKind,
+    before: VClock<A>,
+    after: VClock<A>,
+}
+
+impl<A: Ord> OpHead<A> {
+    /// This head's id, stable for the lifetime of the [`Heads`] it came
+    /// from, suitable for passing to [`Heads::restore`].
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Which call this head was recorded for.
+    pub fn kind(&self) -> OpKind {
+        self.kind
+    }
+
+    /// The vector clock immediately before this call.
+    pub fn before(&self) -> &VClock<A> {
+        &self.before
+    }
+
+    /// The vector clock immediately after this call.
+    pub fn after(&self) -> &VClock<A> {
+        &self.after
+    }
+}
+
+/// Wraps a [`LogReplicable`] log and its materialized CRDT, recording every
+/// `commit`/`pull`/`push` as an [`OpHead`] so a bad merge or an accidental
+/// `rm` can be undone with [`Heads::restore`].
+pub struct Heads<A: Ord, M: CmRDT, L: LogReplicable<A, M>> {
+    actor: A,
+    log: L,
+    map: M,
+    /// Every op this history has folded into `map`, per actor, in the
+    /// order it was applied (index `i` is counter `i + 1`). This is the
+    /// retained op set `restore` replays from.
+    journal: BTreeMap<A, Vec<M::Op>>,
+    clock: VClock<A>,
+    entries: Vec<OpHead<A>>,
+    next_id: u64,
+}
+
+impl<A, M, L> fmt::Debug for Heads<A, M, L>
+where
+    A: fmt::Debug + Ord,
+    M: CmRDT,
+    L: LogReplicable<A, M> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("op_heads::Heads")
+            .field("actor", &self.actor)
+            .field("log", &self.log)
+            .field("clock", &self.clock)
+            .field("entries", &self.entries.len())
+            .finish()
+    }
+}
+
+impl<A, M, L> Heads<A, M, L>
+where
+    A: Ord + Clone,
+    M: CmRDT,
+    M::Op: Clone,
+    L: LogReplicable<A, M>,
+{
+    /// Wrap `log` and its already-materialized `map` in a history, starting
+    /// with no recorded heads. `actor` is the identity future `commit` calls
+    /// are made under.
+    pub fn new(actor: A, log: L, map: M) -> Self {
+        Self {
+            actor,
+            log,
+            map,
+            journal: BTreeMap::new(),
+            clock: VClock::new(),
+            entries: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// The CRDT materialized from every op this history has folded in.
+    pub fn map(&self) -> &M {
+        &self.map
+    }
+
+    /// The underlying log.
+    pub fn log(&self) -> &L {
+        &self.log
+    }
+
+    /// Walk the meta-history newest-first.
+    pub fn operations(&self) -> impl Iterator<Item = &OpHead<A>> {
+        self.entries.iter().rev()
+    }
+
+    fn fold(&mut self, tagged: &TaggedOp<A, M::Op>) {
+        self.map.apply(tagged.op()).expect("CmRDT::apply is infallible (see crdts::CmRDT)");
+        self.clock.apply(tagged.dot());
+        self.journal.entry(tagged.dot().actor.clone()).or_default().push(tagged.op().clone());
+    }
+
+    fn drain(&mut self) -> Result<(), L::Error> {
+        while let Some(tagged) = self.log.next()? {
+            self.fold(&tagged);
+            self.log.ack(&tagged)?;
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, kind: OpKind, before: VClock<A>) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push(OpHead { id, kind, before, after: self.clock.clone() });
+    }
+
+    /// Commit `op` to the log, fold it into the materialized CRDT, and
+    /// record an [`OpKind::Commit`] head.
+    pub fn commit(&mut self, op: M::Op) -> Result<TaggedOp<A, M::Op>, L::Error> {
+        let before = self.clock.clone();
+        let tagged = self.log.commit(self.actor.clone(), op)?;
+        self.fold(&tagged);
+        self.log.ack(&tagged)?;
+        self.record(OpKind::Commit, before);
+        Ok(tagged)
+    }
+
+    /// Pull every op `other`'s log has that ours doesn't, fold the newly
+    /// discovered ones into our materialized CRDT, and record an
+    /// [`OpKind::Pull`] head.
+    pub fn pull(&mut self, other: &Self) -> Result<(), L::Error> {
+        let before = self.clock.clone();
+        self.log.pull(&other.log)?;
+        self.drain()?;
+        self.record(OpKind::Pull, before);
+        Ok(())
+    }
+
+    /// Push every op our log has that `other`'s doesn't, fold the newly
+    /// discovered ones into `other`'s materialized CRDT, and record an
+    /// [`OpKind::Push`] head on `other` (it's `other`'s state that changed).
+    pub fn push(&mut self, other: &mut Self) -> Result<(), L::Error> {
+        let other_before = other.clock.clone();
+        self.log.push(&mut other.log)?;
+        other.drain()?;
+        other.record(OpKind::Push, other_before);
+        Ok(())
+    }
+}
+
+impl<A, M, L> Heads<A, M, L>
+where
+    A: Ord + Clone,
+    M: CmRDT + Default,
+    M::Op: Clone,
+    L: LogReplicable<A, M>,
+{
+    /// Reset the materialized CRDT (and this history's notion of what it
+    /// has seen) back to the state recorded at `op_id`, by replaying the
+    /// retained op set up to the counters in that head's `after` clock.
+    /// Returns `false` if no head with that id has been recorded.
+    ///
+    /// This doesn't touch the underlying log -- its ops are commutative and
+    /// idempotent, so nothing needs to be deleted to undo a bad merge or an
+    /// accidental `rm`; only the convenience view this history keeps on top
+    /// of it rewinds.
+    pub fn restore(&mut self, op_id: u64) -> bool {
+        let target = match self.entries.iter().find(|head| head.id == op_id) {
+            Some(head) => head.after.clone(),
+            None => return false,
+        };
+
+        let mut map = M::default();
+        for (actor, ops) in &self.journal {
+            let keep = target.get(actor) as usize;
+            for op in ops.iter().take(keep) {
+                map.apply(op).expect("CmRDT::apply is infallible (see crdts::CmRDT)");
+            }
+        }
+
+        self.map = map;
+        self.clock = target;
+        true
+    }
+}
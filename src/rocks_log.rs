@@ -0,0 +1,244 @@
+//! A durable [`LogReplicable`] backend backed by a single RocksDB database.
+//!
+//! Unlike [`crate::git_log`], which pays for an fsync'd git object per op,
+//! this backend keeps every actor's ops in one column family keyed by
+//! `(actor, counter)`, the set of known actors in a second, and a per-actor
+//! ack cursor in a third. An actor's high-water mark is not stored: `commit`,
+//! `next` and `pull` find it by scanning that actor's key range in the ops
+//! column family, rather than walking a commit chain. `pull`/`push` compare
+//! per-actor high-water marks and copy only the `(actor, counter)` range the
+//! other side is missing.
+//!
+//! Requires the `rocks` feature, since it pulls in a C++ build of RocksDB.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use rocksdb::{ColumnFamilyDescriptor, Direction, IteratorMode, Options, DB};
+use serde::{Deserialize, Serialize};
+
+use crate::crdts::{CmRDT, Dot};
+use crate::{LogReplicable, TaggedOp};
+
+const OPS_CF: &str = "hermitdb_ops";
+const ACTORS_CF: &str = "hermitdb_actors";
+const ACK_CF: &str = "hermitdb_ack";
+
+/// Everything that can go wrong talking to the underlying RocksDB database.
+#[derive(Debug)]
+pub enum Error {
+    /// RocksDB itself reported an error.
+    Rocks(rocksdb::Error),
+    /// An actor or op couldn't be encoded/decoded.
+    Bincode(bincode::Error),
+    /// The log's own bookkeeping (high-water marks, ack cursors) disagreed
+    /// with what's actually stored.
+    Corrupt(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Rocks(e) => write!(f, "rocksdb error: {}", e),
+            Error::Bincode(e) => write!(f, "encoding error: {}", e),
+            Error::Corrupt(msg) => write!(f, "corrupt log: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<rocksdb::Error> for Error {
+    fn from(e: rocksdb::Error) -> Self {
+        Error::Rocks(e)
+    }
+}
+
+impl From<bincode::Error> for Error {
+    fn from(e: bincode::Error) -> Self {
+        Error::Bincode(e)
+    }
+}
+
+/// A RocksDB-backed, per-actor append log.
+pub struct Log<A, M: CmRDT> {
+    db: DB,
+    path: PathBuf,
+    _map: PhantomData<M>,
+}
+
+impl<A, M: CmRDT> fmt::Debug for Log<A, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("rocks_log::Log").field("path", &self.path).finish()
+    }
+}
+
+impl<A, M> Log<A, M>
+where
+    A: Ord + Clone + Serialize + for<'de> Deserialize<'de>,
+    M: CmRDT,
+    M::Op: Clone + Serialize + for<'de> Deserialize<'de>,
+{
+    /// Open (creating if necessary) a RocksDB database at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+
+        let mut db_opts = Options::default();
+        db_opts.create_if_missing(true);
+        db_opts.create_missing_column_families(true);
+
+        let cfs = [OPS_CF, ACTORS_CF, ACK_CF]
+            .iter()
+            .map(|name| ColumnFamilyDescriptor::new(*name, Options::default()));
+        let db = DB::open_cf_descriptors(&db_opts, &path, cfs)?;
+
+        Ok(Self { db, path, _map: PhantomData })
+    }
+
+    /// The path this log's database lives at.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn ops_cf(&self) -> &rocksdb::ColumnFamily {
+        self.db.cf_handle(OPS_CF).expect("hermitdb_ops column family missing")
+    }
+
+    fn actors_cf(&self) -> &rocksdb::ColumnFamily {
+        self.db.cf_handle(ACTORS_CF).expect("hermitdb_actors column family missing")
+    }
+
+    fn ack_cf(&self) -> &rocksdb::ColumnFamily {
+        self.db.cf_handle(ACK_CF).expect("hermitdb_ack column family missing")
+    }
+
+    fn actor_key(actor: &A) -> Result<Vec<u8>, Error> {
+        Ok(bincode::serialize(actor)?)
+    }
+
+    fn op_key(actor_key: &[u8], counter: u64) -> Vec<u8> {
+        let mut key = actor_key.to_vec();
+        key.extend_from_slice(&counter.to_be_bytes());
+        key
+    }
+
+    /// Remember that `actor` has at least one op in this log, so `next` and
+    /// `pull` know to look at it.
+    fn mark_known(&self, actor: &A) -> Result<(), Error> {
+        let key = Self::actor_key(actor)?;
+        Ok(self.db.put_cf(self.actors_cf(), &key, b"")?)
+    }
+
+    /// Every actor this log has ever committed or pulled an op from.
+    fn known_actors(&self) -> Result<std::collections::BTreeSet<A>, Error> {
+        let mut actors = std::collections::BTreeSet::new();
+        let iter = self.db.full_iterator_cf(self.actors_cf(), IteratorMode::Start);
+        for (key, _) in iter {
+            actors.insert(bincode::deserialize(&key)?);
+        }
+        Ok(actors)
+    }
+
+    /// `actor`'s highest committed counter, or `0` if this log has never
+    /// seen an op from `actor`.
+    fn tip_counter(&self, actor: &A) -> Result<u64, Error> {
+        let actor_key = Self::actor_key(actor)?;
+        let mode = IteratorMode::From(&actor_key, Direction::Forward);
+        let mut tip = 0;
+        for (key, _) in self.db.full_iterator_cf(self.ops_cf(), mode) {
+            if !key.starts_with(&actor_key) {
+                break;
+            }
+            let counter_bytes = &key[actor_key.len()..];
+            let mut buf = [0u8; 8];
+            buf.copy_from_slice(counter_bytes);
+            tip = tip.max(u64::from_be_bytes(buf));
+        }
+        Ok(tip)
+    }
+
+    /// The highest counter `actor`'s ops have been acknowledged up to, or
+    /// `0` if none have.
+    fn ack_cursor(&self, actor: &A) -> Result<u64, Error> {
+        let actor_key = Self::actor_key(actor)?;
+        match self.db.get_cf(self.ack_cf(), &actor_key)? {
+            None => Ok(0),
+            Some(bytes) => {
+                let mut buf = [0u8; 8];
+                buf.copy_from_slice(&bytes);
+                Ok(u64::from_be_bytes(buf))
+            }
+        }
+    }
+
+    fn op_at(&self, actor_key: &[u8], counter: u64) -> Result<M::Op, Error> {
+        let key = Self::op_key(actor_key, counter);
+        let bytes = self
+            .db
+            .get_cf(self.ops_cf(), &key)?
+            .ok_or_else(|| Error::Corrupt(format!("missing op at counter {}", counter)))?;
+        Ok(bincode::deserialize(&bytes)?)
+    }
+}
+
+impl<A, M> LogReplicable<A, M> for Log<A, M>
+where
+    A: Ord + Clone + Serialize + for<'de> Deserialize<'de>,
+    M: CmRDT,
+    M::Op: Clone + Serialize + for<'de> Deserialize<'de>,
+{
+    type Error = Error;
+
+    fn commit(&mut self, actor: A, op: M::Op) -> Result<TaggedOp<A, M::Op>, Self::Error> {
+        let actor_key = Self::actor_key(&actor)?;
+        let counter = self.tip_counter(&actor)? + 1;
+        let key = Self::op_key(&actor_key, counter);
+        self.db.put_cf(self.ops_cf(), &key, bincode::serialize(&op)?)?;
+        self.mark_known(&actor)?;
+        Ok(TaggedOp::new(Dot::new(actor, counter), op))
+    }
+
+    fn ack(&mut self, tagged_op: &TaggedOp<A, M::Op>) -> Result<(), Self::Error> {
+        let dot = tagged_op.dot();
+        let actor_key = Self::actor_key(&dot.actor)?;
+        let cursor = self.ack_cursor(&dot.actor)?.max(dot.counter);
+        self.db.put_cf(self.ack_cf(), &actor_key, cursor.to_be_bytes())?;
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<Option<TaggedOp<A, M::Op>>, Self::Error> {
+        for actor in self.known_actors()? {
+            let acked = self.ack_cursor(&actor)?;
+            let tip = self.tip_counter(&actor)?;
+            if acked < tip {
+                let counter = acked + 1;
+                let actor_key = Self::actor_key(&actor)?;
+                let op = self.op_at(&actor_key, counter)?;
+                return Ok(Some(TaggedOp::new(Dot::new(actor, counter), op)));
+            }
+        }
+        Ok(None)
+    }
+
+    fn pull(&mut self, other: &Self) -> Result<(), Self::Error> {
+        for actor in other.known_actors()? {
+            let our_tip = self.tip_counter(&actor)?;
+            let other_tip = other.tip_counter(&actor)?;
+            if other_tip > our_tip {
+                let actor_key = Self::actor_key(&actor)?;
+                for counter in (our_tip + 1)..=other_tip {
+                    let op = other.op_at(&actor_key, counter)?;
+                    let key = Self::op_key(&actor_key, counter);
+                    self.db.put_cf(self.ops_cf(), &key, bincode::serialize(&op)?)?;
+                }
+                self.mark_known(&actor)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, other: &mut Self) -> Result<(), Self::Error> {
+        other.pull(self)
+    }
+}
@@ -0,0 +1,75 @@
+extern crate hermitdb;
+extern crate tempfile;
+
+#[macro_use]
+extern crate assert_matches;
+
+use hermitdb::crdts::{map, orswot};
+use hermitdb::git_log;
+use hermitdb::LogReplicable;
+
+type TActor = u8;
+type TKey = u8;
+type TVal = hermitdb::crdts::Orswot<u8, TActor>;
+type TMap = hermitdb::crdts::Map<TKey, TVal, TActor>;
+
+/// A credentials callback that's never actually invoked: every transport in
+/// this test is a local filesystem path, which libgit2 doesn't authenticate.
+fn unused_credentials() -> git_log::CredentialCallback {
+    Box::new(|_url, _username, _allowed| {
+        Err(git2::Error::from_str("credentials were not expected for a local transport"))
+    })
+}
+
+/// A transport URL [`hermitdb::git_log::Log::with_credentials`] can use to
+/// reach `path` locally, in lieu of a real `ssh://`/`https://` remote.
+fn file_url(path: &std::path::Path) -> String {
+    path.display().to_string()
+}
+
+#[test]
+fn push_remote_then_pull_remote_round_trips_a_commit() {
+    let origin_dir = tempfile::tempdir().unwrap();
+    // `origin` is only ever talked to over its remote transport below, never
+    // opened directly.
+    git2::Repository::init_bare(origin_dir.path()).unwrap();
+
+    let a_dir = tempfile::tempdir().unwrap();
+    let a_git = git2::Repository::init_bare(a_dir.path()).unwrap();
+    let mut a_log: git_log::Log<TActor, TMap> = git_log::Log::with_credentials(
+        a_git,
+        "a".into(),
+        a_dir.path().to_str().unwrap().to_string(),
+        file_url(origin_dir.path()),
+        unused_credentials(),
+    );
+
+    let op: map::Op<TKey, TVal, TActor> = map::Op::Up {
+        dot: hermitdb::crdts::Dot::new(1, 1),
+        key: 7,
+        op: orswot::Op::Add { member: 42, dot: hermitdb::crdts::Dot::new(1, 1) },
+    };
+    assert_matches!(a_log.commit(1, op.clone()), Ok(_));
+    // `commit` only batches the op into the pending block; `next` is what
+    // flushes it into an actual git commit, which is what `push_remote` has
+    // a ref to push.
+    assert_matches!(a_log.next(), Ok(Some(_)));
+    assert_matches!(a_log.push_remote(), Ok(()));
+
+    let b_dir = tempfile::tempdir().unwrap();
+    let b_git = git2::Repository::init_bare(b_dir.path()).unwrap();
+    let mut b_log: git_log::Log<TActor, TMap> = git_log::Log::with_credentials(
+        b_git,
+        "b".into(),
+        b_dir.path().to_str().unwrap().to_string(),
+        file_url(origin_dir.path()),
+        unused_credentials(),
+    );
+
+    assert_matches!(b_log.pull_remote(&git_log::Log::<TActor, TMap>::default_fetch_refspec()), Ok(()));
+
+    let tagged_op = b_log.next().unwrap().expect("b should have adopted a's pushed op");
+    assert_eq!(tagged_op.op(), &op);
+    assert_matches!(b_log.ack(&tagged_op), Ok(()));
+    assert_matches!(b_log.next(), Ok(None));
+}
@@ -10,9 +10,11 @@ extern crate quickcheck;
 use quickcheck::{Arbitrary, Gen, TestResult};
 
 use hermitdb::crdts::{map, orswot, Map, Orswot, CmRDT};
-use hermitdb::{LogReplicable, TaggedOp};
+use hermitdb::LogReplicable;
 use hermitdb::memory_log;
 use hermitdb::git_log;
+#[cfg(feature = "rocks")]
+use hermitdb::rocks_log;
 
 type TActor = u8;
 type TKey = u8;
@@ -35,7 +37,7 @@ impl Arbitrary for OpVec {
             let op = match die_roll % 3 {
                 0 => {
                     // update Orswot
-                    map.update(key, map.dot(actor.clone()), |set, dot| {
+                    map.update(key, map.dot(actor), |set, dot| {
                         let die_roll: u8 = g.gen();
                         let member = g.gen();
                         match die_roll % 2 {
@@ -51,7 +53,7 @@ impl Arbitrary for OpVec {
                     // rm
                     let ctx = map.get(&key)
                         .map(|(_, c)| c)
-                        .unwrap_or(hermitdb::crdts::VClock::new());
+                        .unwrap_or_default();
                     map.rm(key, ctx)
                 },
                 _ => {
@@ -65,12 +67,12 @@ impl Arbitrary for OpVec {
         OpVec(actor, ops)
     }
 
-    fn shrink(&self) -> Box<Iterator<Item = Self>> {
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
         let mut shrunk: Vec<Self> = Vec::new();
         for i in 0..self.1.len() {
             let mut vec = self.1.clone();
             vec.remove(i);
-            shrunk.push(OpVec(self.0.clone(), vec))
+            shrunk.push(OpVec(self.0, vec))
         }
         Box::new(shrunk.into_iter())
     }    
@@ -79,6 +81,8 @@ impl Arbitrary for OpVec {
 fn p2p_pull_converge<L: LogReplicable<TActor, TMap>>(
     mut a_log: L,
     mut b_log: L,
+    a_actor: TActor,
+    b_actor: TActor,
     a_ops: Vec<TOp>,
     b_ops: Vec<TOp>
 ) -> TMap {
@@ -86,13 +90,13 @@ fn p2p_pull_converge<L: LogReplicable<TActor, TMap>>(
     let mut b_map = TMap::new();
 
     for op in a_ops {
-        let tagged_op = a_log.commit(op).unwrap();
+        let tagged_op = a_log.commit(a_actor, op).unwrap();
         assert_matches!(a_map.apply(tagged_op.op()), Ok(()));
         assert_matches!(a_log.ack(&tagged_op), Ok(()));
     }
 
     for op in b_ops {
-        let tagged_op = b_log.commit(op).unwrap();
+        let tagged_op = b_log.commit(b_actor, op).unwrap();
         assert_eq!(b_map.apply(tagged_op.op()), Ok(()));
         assert_matches!(b_log.ack(&tagged_op), Ok(()));
     }
@@ -118,6 +122,8 @@ fn centralized_converge<L: LogReplicable<TActor, TMap>>(
     mut a_log: L,
     mut b_log: L,
     mut c_log: L,
+    a_actor: TActor,
+    b_actor: TActor,
     a_ops: Vec<TOp>,
     b_ops: Vec<TOp>
 ) -> TMap {
@@ -125,13 +131,13 @@ fn centralized_converge<L: LogReplicable<TActor, TMap>>(
     let mut b_map = TMap::new();
 
     for op in a_ops {
-        let tagged_op = a_log.commit(op).unwrap();
+        let tagged_op = a_log.commit(a_actor, op).unwrap();
         assert_matches!(a_map.apply(tagged_op.op()), Ok(()));
         assert_matches!(a_log.ack(&tagged_op), Ok(()));
     }
 
     for op in b_ops {
-        let tagged_op = b_log.commit(op).unwrap();
+        let tagged_op = b_log.commit(b_actor, op).unwrap();
         assert_eq!(b_map.apply(tagged_op.op()), Ok(()));
         assert_matches!(b_log.ack(&tagged_op), Ok(()));
     }
@@ -156,21 +162,24 @@ fn centralized_converge<L: LogReplicable<TActor, TMap>>(
     a_map
 }
 
+#[allow(clippy::too_many_arguments)]
 fn all_replication_strategies_converge<L: LogReplicable<TActor, TMap>>(
     a_pull: L, b_pull: L,
     a_central: L, b_central: L, c_central: L,
+    a_actor: TActor,
+    b_actor: TActor,
     a_ops: Vec<TOp>,
     b_ops: Vec<TOp>
 ) {
-    let pull_map = p2p_pull_converge(a_pull, b_pull, a_ops.clone(), b_ops.clone());
-    let central_map = centralized_converge(a_central, b_central, c_central, a_ops, b_ops);
+    let pull_map = p2p_pull_converge(a_pull, b_pull, a_actor, b_actor, a_ops.clone(), b_ops.clone());
+    let central_map = centralized_converge(a_central, b_central, c_central, a_actor, b_actor, a_ops, b_ops);
 
     assert_eq!(pull_map, central_map);
 }
 
-fn log_preserves_order(mut log: impl LogReplicable<TActor, TMap>, ops: Vec<TOp>) {
+fn log_preserves_order(mut log: impl LogReplicable<TActor, TMap>, actor: TActor, ops: Vec<TOp>) {
     for op in ops.iter() {
-        assert_matches!(log.commit(op.clone()), Ok(_));
+        assert_matches!(log.commit(actor, op.clone()), Ok(_));
     }
 
     for op in ops.iter() {
@@ -190,17 +199,16 @@ quickcheck! {
             return TestResult::discard();
         }
 
-        let a_pull = memory_log::Log::new(actor1);
-        let b_pull = memory_log::Log::new(actor2);
-        let a_central = memory_log::Log::new(actor1);
-        let b_central = memory_log::Log::new(actor2);
+        let a_pull = memory_log::Log::new();
+        let b_pull = memory_log::Log::new();
+        let a_central = memory_log::Log::new();
+        let b_central = memory_log::Log::new();
+        let c_central = memory_log::Log::new();
 
-        // TAI: to avoid this dummy actor, consider moving the actor to the trait functions that require an actor.
-        let c_central = memory_log::Log::new(0); // this actor shouldn't matter
-        
         all_replication_strategies_converge(
             a_pull, b_pull,
             a_central, b_central, c_central,
+            actor1, actor2,
             a_ops, b_ops
         );
         TestResult::from_bool(true)
@@ -220,40 +228,89 @@ quickcheck! {
         let b_central_dir = tempfile::tempdir().unwrap();
         let c_central_dir = tempfile::tempdir().unwrap();
         
-        let a_pull_git = hermitdb::git2::Repository::init_bare(&a_pull_dir.path()).unwrap();
-        let b_pull_git = hermitdb::git2::Repository::init_bare(&b_pull_dir.path()).unwrap();
-        let a_central_git = hermitdb::git2::Repository::init_bare(&a_central_dir.path()).unwrap();
-        let b_central_git = hermitdb::git2::Repository::init_bare(&b_central_dir.path()).unwrap();
-        let c_central_git = hermitdb::git2::Repository::init_bare(&c_central_dir.path()).unwrap();
-        
-        let a_pull = git_log::Log::no_auth(actor1, a_pull_git, "a_pull".into(), a_pull_dir.path().to_str().unwrap().to_string());
-        let b_pull = git_log::Log::no_auth(actor2, b_pull_git, "b_pull".into(), b_pull_dir.path().to_str().unwrap().to_string());
-        let a_central = git_log::Log::no_auth(actor1, a_central_git, "a_central".into(), a_central_dir.path().to_str().unwrap().to_string());
-        let b_central = git_log::Log::no_auth(actor2, b_central_git, "b_central".into(), b_central_dir.path().to_str().unwrap().to_string());
-        let c_central = git_log::Log::no_auth(0, c_central_git, "c_central".into(), c_central_dir.path().to_str().unwrap().to_string());
+        let a_pull_git = hermitdb::git2::Repository::init_bare(a_pull_dir.path()).unwrap();
+        let b_pull_git = hermitdb::git2::Repository::init_bare(b_pull_dir.path()).unwrap();
+        let a_central_git = hermitdb::git2::Repository::init_bare(a_central_dir.path()).unwrap();
+        let b_central_git = hermitdb::git2::Repository::init_bare(b_central_dir.path()).unwrap();
+        let c_central_git = hermitdb::git2::Repository::init_bare(c_central_dir.path()).unwrap();
         
+        let a_pull = git_log::Log::no_auth(a_pull_git, "a_pull".into(), a_pull_dir.path().to_str().unwrap().to_string());
+        let b_pull = git_log::Log::no_auth(b_pull_git, "b_pull".into(), b_pull_dir.path().to_str().unwrap().to_string());
+        let a_central = git_log::Log::no_auth(a_central_git, "a_central".into(), a_central_dir.path().to_str().unwrap().to_string());
+        let b_central = git_log::Log::no_auth(b_central_git, "b_central".into(), b_central_dir.path().to_str().unwrap().to_string());
+        let c_central = git_log::Log::no_auth(c_central_git, "c_central".into(), c_central_dir.path().to_str().unwrap().to_string());
+
         all_replication_strategies_converge(
             a_pull, b_pull,
             a_central, b_central, c_central,
+            actor1, actor2,
             a_ops, b_ops
         );
         TestResult::from_bool(true)
     }
 
     fn prop_log_preserves_order_memory(ops: OpVec) -> bool {
-        let log: memory_log::Log<u8, TMap> = memory_log::Log::new(ops.0);
-        log_preserves_order(log, ops.1);
+        let log: memory_log::Log<u8, TMap> = memory_log::Log::new();
+        log_preserves_order(log, ops.0, ops.1);
         true
     }
 
     fn prop_log_preserves_order_git(ops: OpVec) -> bool {
         let log_dir = tempfile::tempdir().unwrap();
         let log_path = log_dir.path();
-        let log_git = hermitdb::git2::Repository::init_bare(&log_path).unwrap();
+        let log_git = hermitdb::git2::Repository::init_bare(log_path).unwrap();
         let log_path_string = log_path.to_str().unwrap().to_string();
-        let log = git_log::Log::no_auth(ops.0, log_git, "log".into(), log_path_string);;
-        
-        log_preserves_order(log, ops.1);
+        let log = git_log::Log::no_auth(log_git, "log".into(), log_path_string);
+
+        log_preserves_order(log, ops.0, ops.1);
+
+        true
+    }
+
+    fn prop_git_block_round_trips(ops: OpVec) -> bool {
+        let ops = ops.1;
+        let encoded = git_log::Log::<TActor, TMap>::encode_ops(1, ops.clone()).unwrap();
+        let (base_counter, decoded) = git_log::Log::<TActor, TMap>::decode_ops(&encoded).unwrap();
+        base_counter == 1 && decoded == ops
+    }
+}
+
+#[cfg(feature = "rocks")]
+quickcheck! {
+    fn prop_replication_strategies_converge_rocks(a_ops: OpVec, b_ops: OpVec) -> TestResult {
+        let (actor1, a_ops) = (a_ops.0, a_ops.1);
+        let (actor2, b_ops) = (b_ops.0, b_ops.1);
+
+        if actor1 == actor2 {
+            return TestResult::discard();
+        }
+
+        let a_pull_dir = tempfile::tempdir().unwrap();
+        let b_pull_dir = tempfile::tempdir().unwrap();
+        let a_central_dir = tempfile::tempdir().unwrap();
+        let b_central_dir = tempfile::tempdir().unwrap();
+        let c_central_dir = tempfile::tempdir().unwrap();
+
+        let a_pull = rocks_log::Log::open(a_pull_dir.path()).unwrap();
+        let b_pull = rocks_log::Log::open(b_pull_dir.path()).unwrap();
+        let a_central = rocks_log::Log::open(a_central_dir.path()).unwrap();
+        let b_central = rocks_log::Log::open(b_central_dir.path()).unwrap();
+        let c_central = rocks_log::Log::open(c_central_dir.path()).unwrap();
+
+        all_replication_strategies_converge(
+            a_pull, b_pull,
+            a_central, b_central, c_central,
+            actor1, actor2,
+            a_ops, b_ops
+        );
+        TestResult::from_bool(true)
+    }
+
+    fn prop_log_preserves_order_rocks(ops: OpVec) -> bool {
+        let log_dir = tempfile::tempdir().unwrap();
+        let log: rocks_log::Log<u8, TMap> = rocks_log::Log::open(log_dir.path()).unwrap();
+
+        log_preserves_order(log, ops.0, ops.1);
 
         true
     }
@@ -261,8 +318,8 @@ quickcheck! {
 
 #[test]
 fn test_quickcheck_1() {
-    let mut a_log: memory_log::Log<u8, TMap> = memory_log::Log::new(89);
-    let mut b_log: memory_log::Log<u8, TMap> = memory_log::Log::new(51);
+    let mut a_log: memory_log::Log<u8, TMap> = memory_log::Log::new();
+    let mut b_log: memory_log::Log<u8, TMap> = memory_log::Log::new();
     let mut a_map = TMap::new();
     let mut b_map = TMap::new();
 
@@ -274,7 +331,7 @@ fn test_quickcheck_1() {
             member: 21
         }
     };
-    let tagged_op = b_log.commit(op).unwrap();
+    let tagged_op = b_log.commit(51, op).unwrap();
     assert_matches!(b_map.apply(tagged_op.op()), Ok(()));
     assert_matches!(b_log.ack(&tagged_op), Ok(()));
 
@@ -295,8 +352,8 @@ fn test_quickcheck_1() {
 
 #[test]
 fn test_quickcheck_2() {
-    let mut a_log: memory_log::Log<u8, TMap> = memory_log::Log::new(89);
-    let mut b_log: memory_log::Log<u8, TMap> = memory_log::Log::new(51);
+    let mut a_log: memory_log::Log<u8, TMap> = memory_log::Log::new();
+    let mut b_log: memory_log::Log<u8, TMap> = memory_log::Log::new();
     let mut a_map = TMap::new();
     let mut b_map = TMap::new();
 
@@ -304,7 +361,7 @@ fn test_quickcheck_2() {
         context: vec![(44, 17)].into_iter().collect(),
         key: 196
     };
-    let tagged_op = b_log.commit(op).unwrap();
+    let tagged_op = b_log.commit(51, op).unwrap();
     
     assert_matches!(b_map.apply(tagged_op.op()), Ok(()));
     assert_matches!(b_log.ack(&tagged_op), Ok(()));
@@ -339,21 +396,20 @@ fn test_quickcheck_3() {
     let b_log_path = b_log_dir.path();
     
     
-    let a_log_git = hermitdb::git2::Repository::init_bare(&a_log_path).unwrap();
-    let b_log_git = hermitdb::git2::Repository::init_bare(&b_log_path).unwrap();
+    let a_log_git = hermitdb::git2::Repository::init_bare(a_log_path).unwrap();
+    let b_log_git = hermitdb::git2::Repository::init_bare(b_log_path).unwrap();
 
 
-    let actor1 = 1;
     let actor2 = 2;
-    let mut a_log: git_log::Log<TActor, TMap> = git_log::Log::no_auth(actor1, a_log_git, "a_log".into(), a_log_path.to_str().unwrap().to_string());
-    let mut b_log: git_log::Log<TActor, TMap> = git_log::Log::no_auth(actor2, b_log_git, "b_log".into(), b_log_path.to_str().unwrap().to_string());
+    let mut a_log: git_log::Log<TActor, TMap> = git_log::Log::no_auth(a_log_git, "a_log".into(), a_log_path.to_str().unwrap().to_string());
+    let mut b_log: git_log::Log<TActor, TMap> = git_log::Log::no_auth(b_log_git, "b_log".into(), b_log_path.to_str().unwrap().to_string());
 
     let mut a_map = TMap::new();
     let mut b_map = TMap::new();
 
     let op: TOp = map::Op::Nop;
 
-    assert_matches!(b_log.commit(op), Ok(_));
+    assert_matches!(b_log.commit(actor2, op), Ok(_));
     assert_eq!(b_log.next().unwrap().unwrap().op(), &map::Op::Nop);
     let tagged_op = b_log.next().unwrap().unwrap();
     assert_matches!(b_map.apply(tagged_op.op()), Ok(()));
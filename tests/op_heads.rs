@@ -0,0 +1,56 @@
+extern crate hermitdb;
+
+use hermitdb::crdts::{map, orswot, Map, Orswot};
+use hermitdb::op_heads::{Heads, OpKind};
+use hermitdb::memory_log;
+
+type TActor = u8;
+type TKey = u8;
+type TVal = Orswot<u8, TActor>;
+type TMap = Map<TKey, TVal, TActor>;
+
+#[test]
+fn restore_undoes_a_bad_merge() {
+    let mut a: Heads<TActor, TMap, memory_log::Log<TActor, TMap>> =
+        Heads::new(1, memory_log::Log::new(), TMap::new());
+
+    let good = a.map().dot(1);
+    a.commit(map::Op::Up {
+        dot: good,
+        key: 7,
+        op: orswot::Op::Add { member: 42, dot: good },
+    }).unwrap();
+    let after_good_commit = a.operations().next().unwrap().id();
+
+    let ctx = a.map().get(&7).map(|(_, c)| c).unwrap_or_default();
+    a.commit(map::Op::Rm { context: ctx, key: 7 }).unwrap();
+
+    assert!(a.map().get(&7).is_none());
+
+    assert!(a.restore(after_good_commit));
+    assert!(a.map().get(&7).is_some());
+}
+
+#[test]
+fn operations_are_walked_newest_first() {
+    let mut log: Heads<TActor, TMap, memory_log::Log<TActor, TMap>> =
+        Heads::new(1, memory_log::Log::new(), TMap::new());
+
+    let dot = log.map().dot(1);
+    log.commit(map::Op::Up {
+        dot,
+        key: 1,
+        op: orswot::Op::Add { member: 1, dot },
+    }).unwrap();
+
+    let dot = log.map().dot(1);
+    log.commit(map::Op::Up {
+        dot,
+        key: 2,
+        op: orswot::Op::Add { member: 2, dot },
+    }).unwrap();
+
+    let ids: Vec<u64> = log.operations().map(|head| head.id()).collect();
+    assert_eq!(ids, vec![1, 0]);
+    assert!(log.operations().all(|head| head.kind() == OpKind::Commit));
+}